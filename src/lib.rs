@@ -0,0 +1,206 @@
+// Copyright 2012-2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Terminal formatting library.
+//!
+//! This crate provides the `Terminal` trait, which abstracts over the
+//! ability of a terminal to display colors and other attributes, plus at
+//! least one implementation, `TerminfoTerminal`, which uses the `terminfo`
+//! database on Unix-likes.
+//!
+//! This crate targets the pre-1.0 Rust in use circa late 2014 (it relies on
+//! `int`/`uint`, `#[derive(Show)]`, `std::io::IoResult`, and `os::getenv`)
+//! and has no `Cargo.toml` of its own; it is built as part of a larger
+//! workspace that pins a matching nightly toolchain.
+
+#![crate_name = "term"]
+#![crate_type = "rlib"]
+#![crate_type = "dylib"]
+
+use std::io::{stdio, IoResult};
+
+pub use terminfo::{TerminfoTerminal, Error};
+#[cfg(windows)]
+pub use win::WinConsole;
+
+pub mod terminfo;
+
+#[cfg(windows)]
+mod win;
+
+/// Wraps either stdout or stderr, so that `stdout()`/`stderr()` below can
+/// hand back a single boxed `Terminal` type regardless of which stream the
+/// caller asked for.
+pub enum WriterWrapper {
+    /// Wraps the process's standard output stream
+    Stdout,
+    /// Wraps the process's standard error stream
+    Stderr,
+}
+
+impl Writer for WriterWrapper {
+    fn write(&mut self, buf: &[u8]) -> IoResult<()> {
+        match *self {
+            WriterWrapper::Stdout => stdio::stdout_raw().write(buf),
+            WriterWrapper::Stderr => stdio::stderr_raw().write(buf),
+        }
+    }
+}
+
+/// A `Terminal` wrapping stdout.
+pub type StdoutTerminal = Terminal<WriterWrapper> + Send;
+/// A `Terminal` wrapping stderr.
+pub type StderrTerminal = Terminal<WriterWrapper> + Send;
+
+/// Returns a `Terminal` wrapping stdout, or the `Error` describing why one
+/// couldn't be opened.
+///
+/// This tries `TerminfoTerminal` first and, on Windows, falls back to
+/// `WinConsole` (which cannot fail) whenever `TerminfoTerminal::new` fails.
+pub fn stdout() -> Result<Box<StdoutTerminal>, Error> {
+    match TerminfoTerminal::new(WriterWrapper::Stdout) {
+        Ok(t) => Ok(Box::new(t)),
+        Err(e) => win_stdout(e),
+    }
+}
+
+/// Returns a `Terminal` wrapping stderr, or the `Error` describing why one
+/// couldn't be opened.
+///
+/// This tries `TerminfoTerminal` first and, on Windows, falls back to
+/// `WinConsole` (which cannot fail) whenever `TerminfoTerminal::new` fails.
+pub fn stderr() -> Result<Box<StderrTerminal>, Error> {
+    match TerminfoTerminal::new(WriterWrapper::Stderr) {
+        Ok(t) => Ok(Box::new(t)),
+        Err(e) => win_stderr(e),
+    }
+}
+
+#[cfg(windows)]
+fn win_stdout(_err: Error) -> Result<Box<StdoutTerminal>, Error> {
+    Ok(Box::new(WinConsole::new(WriterWrapper::Stdout, win::STD_OUTPUT_HANDLE)))
+}
+#[cfg(not(windows))]
+fn win_stdout(err: Error) -> Result<Box<StdoutTerminal>, Error> { Err(err) }
+
+#[cfg(windows)]
+fn win_stderr(_err: Error) -> Result<Box<StderrTerminal>, Error> {
+    Ok(Box::new(WinConsole::new(WriterWrapper::Stderr, win::STD_ERROR_HANDLE)))
+}
+#[cfg(not(windows))]
+fn win_stderr(err: Error) -> Result<Box<StderrTerminal>, Error> { Err(err) }
+
+/// Terminal color definitions
+pub mod color {
+    /// Number for a terminal color
+    pub type Color = u16;
+
+    pub const BLACK:   Color = 0;
+    pub const RED:     Color = 1;
+    pub const GREEN:   Color = 2;
+    pub const YELLOW:  Color = 3;
+    pub const BLUE:    Color = 4;
+    pub const MAGENTA: Color = 5;
+    pub const CYAN:    Color = 6;
+    pub const WHITE:   Color = 7;
+
+    pub const BRIGHT_BLACK:   Color = 8;
+    pub const BRIGHT_RED:     Color = 9;
+    pub const BRIGHT_GREEN:   Color = 10;
+    pub const BRIGHT_YELLOW:  Color = 11;
+    pub const BRIGHT_BLUE:    Color = 12;
+    pub const BRIGHT_MAGENTA: Color = 13;
+    pub const BRIGHT_CYAN:    Color = 14;
+    pub const BRIGHT_WHITE:   Color = 15;
+}
+
+/// Terminal attributes for use with term.attr().
+///
+/// Most attributes can only be turned on and must be turned off with
+/// `term.reset()`. Color is also represented as an attribute for
+/// convenience.
+#[derive(Copy, Show)]
+pub enum Attr {
+    /// Bold (or possibly bright) mode
+    Bold,
+    /// Dim mode, also called faint or half-bright. Often not supported
+    Dim,
+    /// Italic mode (bool specifies whether on or off)
+    Italic(bool),
+    /// Underline mode (bool specifies whether on or off)
+    Underline(bool),
+    /// Blink mode
+    Blink,
+    /// Standout mode (bool specifies whether on or off)
+    Standout(bool),
+    /// Reverse mode
+    Reverse,
+    /// Secure mode (hidden text)
+    Secure,
+    /// Sets the foreground color
+    ForegroundColor(color::Color),
+    /// Sets the background color
+    BackgroundColor(color::Color),
+}
+
+/// A terminal with the given output type, supporting color and other
+/// formatting attributes.
+///
+/// Every method of this trait returns `Ok(false)` (as opposed to an
+/// `Err` value) when the terminal cannot perform the given action, so
+/// callers can decide whether the unsupported action is a hard failure.
+pub trait Terminal<T: Writer>: Writer {
+    /// Sets the foreground color to the given color.
+    ///
+    /// If the color is a bright color, but the terminal only supports 8
+    /// colors, the corresponding normal color will be used instead.
+    fn fg(&mut self, color: color::Color) -> IoResult<bool>;
+
+    /// Sets the background color to the given color.
+    ///
+    /// If the color is a bright color, but the terminal only supports 8
+    /// colors, the corresponding normal color will be used instead.
+    fn bg(&mut self, color: color::Color) -> IoResult<bool>;
+
+    /// Sets the given terminal attribute, if supported. Returns `Ok(true)`
+    /// if the attribute was supported, `Ok(false)` otherwise.
+    fn attr(&mut self, attr: Attr) -> IoResult<bool>;
+
+    /// Returns whether the given terminal attribute is supported.
+    fn supports_attr(&self, attr: Attr) -> bool;
+
+    /// Resets all terminal attributes and colors to their defaults.
+    fn reset(&mut self) -> IoResult<bool>;
+
+    /// Moves the cursor up one line. Returns `Ok(false)` if unsupported.
+    fn cursor_up(&mut self) -> IoResult<bool> { Ok(false) }
+
+    /// Moves the cursor down one line. Returns `Ok(false)` if unsupported.
+    fn cursor_down(&mut self) -> IoResult<bool> { Ok(false) }
+
+    /// Moves the cursor to the given zero-indexed `(row, col)`. Returns
+    /// `Ok(false)` if unsupported.
+    fn move_cursor(&mut self, _row: uint, _col: uint) -> IoResult<bool> { Ok(false) }
+
+    /// Clears the entire screen. Returns `Ok(false)` if unsupported.
+    fn clear_screen(&mut self) -> IoResult<bool> { Ok(false) }
+
+    /// Gets an immutable reference to the stream inside this terminal.
+    fn get_ref<'a>(&'a self) -> &'a T;
+
+    /// Gets a mutable reference to the stream inside this terminal.
+    fn get_mut<'a>(&'a mut self) -> &'a mut T;
+}
+
+/// A `Terminal` that can be unwrapped to retrieve the stream it wraps.
+pub trait UnwrappableTerminal<T: Writer>: Terminal<T> {
+    /// Returns the contained stream, destroying the `Terminal`.
+    fn unwrap(self) -> T;
+}
@@ -11,7 +11,7 @@
 //! Terminfo database interface.
 
 use std::collections::HashMap;
-use std::io::IoResult;
+use std::io::{IoResult, IoError};
 use std::os;
 
 use Attr;
@@ -42,9 +42,24 @@ pub mod searcher;
 pub mod parser {
     //! ncurses-compatible compiled terminfo format parsing (term(5))
     pub mod compiled;
+    mod names;
 }
 pub mod parm;
 
+/// The error type returned when a `TerminfoTerminal` could not be
+/// constructed.
+#[derive(Show)]
+pub enum Error {
+    /// The `TERM` environment variable was not set.
+    TermUnset,
+    /// No terminfo entry could be found for the terminal named by `TERM`.
+    EntryNotFound,
+    /// An I/O error occurred while locating the terminfo entry.
+    Io(IoError),
+    /// A terminfo entry was found but could not be read or parsed.
+    MalformedTerminfo(String),
+}
+
 
 fn cap_for_attr(attr: Attr) -> &'static str {
     match attr {
@@ -127,6 +142,22 @@ impl<T: Writer+Send> Terminal<T> for TerminfoTerminal<T> {
         self.out.write(&cmd[]).map(|_|true)
     }
 
+    fn cursor_up(&mut self) -> IoResult<bool> {
+        self.apply_cap("cuu1", &[])
+    }
+
+    fn cursor_down(&mut self) -> IoResult<bool> {
+        self.apply_cap("cud1", &[])
+    }
+
+    fn move_cursor(&mut self, row: uint, col: uint) -> IoResult<bool> {
+        self.apply_cap("cup", &[Param::Number(row as int), Param::Number(col as int)])
+    }
+
+    fn clear_screen(&mut self) -> IoResult<bool> {
+        self.apply_cap("clear", &[])
+    }
+
     fn get_ref<'a>(&'a self) -> &'a T { &self.out }
 
     fn get_mut<'a>(&'a mut self) -> &'a mut T { &mut self.out }
@@ -137,51 +168,49 @@ impl<T: Writer+Send> UnwrappableTerminal<T> for TerminfoTerminal<T> {
 }
 
 impl<T: Writer+Send> TerminfoTerminal<T> {
-    /// Returns `None` whenever the terminal cannot be created for some
-    /// reason.
-    pub fn new(out: T) -> Option<TerminfoTerminal<T>> {
+    /// Returns `Err` with the reason why the terminal couldn't be created,
+    /// e.g. a missing `TERM`, a missing terminfo entry, or a malformed one.
+    pub fn new(out: T) -> Result<TerminfoTerminal<T>, Error> {
         let term = match os::getenv("TERM") {
             Some(t) => t,
-            None => {
-                debug!("TERM environment variable not defined");
-                return None;
-            }
+            None => return Err(Error::TermUnset),
         };
 
-        let entry = open(&term[]);
-        if entry.is_err() {
-            if os::getenv("MSYSCON").map_or(false, |s| {
-                    "mintty.exe" == s
-                }) {
-                // msys terminal
-                return Some(TerminfoTerminal {
-                    out: out,
-                    ti: msys_terminfo(),
-                    num_colors: 8
+        let mut file = match open(&term[]) {
+            Ok(file) => file,
+            Err(e) => {
+                if os::getenv("MSYSCON").map_or(false, |s| {
+                        "mintty.exe" == s
+                    }) {
+                    // msys terminal
+                    return Ok(TerminfoTerminal {
+                        out: out,
+                        ti: msys_terminfo(),
+                        num_colors: 8
+                    });
+                }
+                return Err(match e.kind {
+                    ::std::io::FileNotFound => Error::EntryNotFound,
+                    _ => Error::Io(e),
                 });
             }
-            debug!("error finding terminfo entry: {}", entry.err().unwrap());
-            return None;
-        }
+        };
 
-        let mut file = entry.unwrap();
-        let ti = parse(&mut file, false);
-        if ti.is_err() {
-            debug!("error parsing terminfo entry: {}", ti.unwrap_err());
-            return None;
-        }
+        let inf = match parse(&mut file, false) {
+            Ok(inf) => inf,
+            Err(e) => return Err(Error::MalformedTerminfo(format!("{}", e))),
+        };
 
-        let inf = ti.unwrap();
         let nc = if inf.strings.get("setaf").is_some()
                  && inf.strings.get("setab").is_some() {
                      inf.numbers.get("colors").map_or(0, |&n| n)
                  } else { 0 };
 
-        return Some(TerminfoTerminal {
+        Ok(TerminfoTerminal {
             out: out,
             ti: inf,
             num_colors: nc
-        });
+        })
     }
 
     fn dim_if_necessary(&self, color: color::Color) -> color::Color {
@@ -190,6 +219,26 @@ impl<T: Writer+Send> TerminfoTerminal<T> {
         } else { color }
     }
 
+    /// Hides the cursor, if the terminal supports it.
+    pub fn cursor_hide(&mut self) -> IoResult<bool> {
+        self.apply_cap("civis", &[])
+    }
+
+    /// Shows the cursor, if the terminal supports it.
+    pub fn cursor_show(&mut self) -> IoResult<bool> {
+        self.apply_cap("cnorm", &[])
+    }
+
+    /// Looks up the named string capability, expands it with `params`, and
+    /// writes the result to the underlying stream. Returns `Ok(false)` if
+    /// the terminal's terminfo entry doesn't define `cap`.
+    ///
+    /// This is the general escape hatch for capabilities (cursor addressing,
+    /// scrolling regions, and so on) that don't have a dedicated method.
+    pub fn apply(&mut self, cap: &str, params: &[Param]) -> IoResult<bool> {
+        self.apply_cap(cap, params)
+    }
+
     fn apply_cap(&mut self, cmd: &str, params: &[Param]) -> IoResult<bool> {
         if let Some(cmd) = self.ti.strings.get(cmd) {
             if let Ok(s) = expand(cmd.as_slice(), params, &mut Variables::new()) {
@@ -0,0 +1,289 @@
+// Copyright 2013-2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Parameterized string expansion, as supported by ncurses-compatible
+//! terminfo string capabilities (see `terminfo(5)`, "Parameterized
+//! Strings").
+
+#[derive(Clone, PartialEq, Show)]
+/// Types of parameters a capability string can take.
+pub enum Param {
+    /// A number, pushed with `%p1%d`-style references.
+    Number(int),
+    /// A string, used with `%s`.
+    String(Vec<u8>),
+}
+
+/// Container for static and dynamic variable arrays, persisted across
+/// calls to `expand` for capabilities that use `%P`/`%g`.
+pub struct Variables {
+    /// Static variables A-Z, persist across calls to `expand`.
+    sta: [Param; 26],
+    /// Dynamic variables a-z; like the static ones, `expand` does not reset them.
+    dyn: [Param; 26],
+}
+
+impl Variables {
+    /// Returns a fresh set of variables, all initialized to the number 0.
+    pub fn new() -> Variables {
+        Variables {
+            sta: [
+                Param::Number(0), Param::Number(0), Param::Number(0), Param::Number(0),
+                Param::Number(0), Param::Number(0), Param::Number(0), Param::Number(0),
+                Param::Number(0), Param::Number(0), Param::Number(0), Param::Number(0),
+                Param::Number(0), Param::Number(0), Param::Number(0), Param::Number(0),
+                Param::Number(0), Param::Number(0), Param::Number(0), Param::Number(0),
+                Param::Number(0), Param::Number(0), Param::Number(0), Param::Number(0),
+                Param::Number(0), Param::Number(0),
+            ],
+            dyn: [
+                Param::Number(0), Param::Number(0), Param::Number(0), Param::Number(0),
+                Param::Number(0), Param::Number(0), Param::Number(0), Param::Number(0),
+                Param::Number(0), Param::Number(0), Param::Number(0), Param::Number(0),
+                Param::Number(0), Param::Number(0), Param::Number(0), Param::Number(0),
+                Param::Number(0), Param::Number(0), Param::Number(0), Param::Number(0),
+                Param::Number(0), Param::Number(0), Param::Number(0), Param::Number(0),
+                Param::Number(0), Param::Number(0),
+            ],
+        }
+    }
+}
+
+/// Expands a parameterized capability string (as found in `TermInfo::strings`)
+/// with the given parameters, producing the raw bytes that should be written
+/// to the terminal.
+pub fn expand(cap: &[u8], params: &[Param], vars: &mut Variables) -> Result<Vec<u8>, String> {
+    let mut state = State {
+        stack: Vec::new(),
+        params: {
+            let mut v = params.to_vec();
+            while v.len() < 9 { v.push(Param::Number(0)); }
+            v
+        },
+    };
+
+    let mut output = Vec::new();
+    let mut input = cap.iter().peekable();
+
+    while let Some(&c) = input.next() {
+        if c != b'%' {
+            output.push(c);
+            continue;
+        }
+
+        match input.next() {
+            None => return Err("premature end of terminfo capability string".to_string()),
+            Some(&b'%') => output.push(b'%'),
+            Some(&b'c') => match try!(state.pop()) {
+                Param::Number(n) => output.push(n as u8),
+                Param::String(_) => return Err("a string was used as %c's argument".to_string()),
+            },
+            Some(&b's') => match try!(state.pop()) {
+                Param::String(s) => output.push_all(&s),
+                Param::Number(_) => return Err("a number was used as %s's argument".to_string()),
+            },
+            Some(&b'p') => match input.next() {
+                Some(&c) if c >= b'1' && c <= b'9' => {
+                    let idx = (c - b'1') as uint;
+                    state.stack.push(state.params[idx].clone());
+                }
+                _ => return Err("invalid parameter number in %p".to_string()),
+            },
+            Some(&b'P') => match input.next() {
+                Some(&c) if c >= b'a' && c <= b'z' => {
+                    let v = try!(state.pop());
+                    vars.dyn[(c - b'a') as uint] = v;
+                }
+                Some(&c) if c >= b'A' && c <= b'Z' => {
+                    let v = try!(state.pop());
+                    vars.sta[(c - b'A') as uint] = v;
+                }
+                _ => return Err("invalid variable name in %P".to_string()),
+            },
+            Some(&b'g') => match input.next() {
+                Some(&c) if c >= b'a' && c <= b'z' => {
+                    state.stack.push(vars.dyn[(c - b'a') as uint].clone());
+                }
+                Some(&c) if c >= b'A' && c <= b'Z' => {
+                    state.stack.push(vars.sta[(c - b'A') as uint].clone());
+                }
+                _ => return Err("invalid variable name in %g".to_string()),
+            },
+            Some(&b'\'') => {
+                let c = *try!(input.next().ok_or("unterminated %' char literal".to_string()));
+                input.next(); // consume the closing quote
+                state.stack.push(Param::Number(c as int));
+            }
+            Some(&b'{') => {
+                let mut n: int = 0;
+                loop {
+                    match input.next() {
+                        Some(&b'}') => break,
+                        Some(&d) if d >= b'0' && d <= b'9' => n = n * 10 + (d - b'0') as int,
+                        _ => return Err("malformed %{...} literal".to_string()),
+                    }
+                }
+                state.stack.push(Param::Number(n));
+            }
+            Some(&b'l') => {
+                let len = match try!(state.pop()) {
+                    Param::String(s) => s.len() as int,
+                    Param::Number(_) => return Err("a number was used as %l's argument".to_string()),
+                };
+                state.stack.push(Param::Number(len));
+            }
+            Some(&b'i') => {
+                if let Param::Number(n) = state.params[0] {
+                    state.params[0] = Param::Number(n + 1);
+                }
+                if let Param::Number(n) = state.params[1] {
+                    state.params[1] = Param::Number(n + 1);
+                }
+            }
+            Some(&op @ b'+') | Some(&op @ b'-') | Some(&op @ b'*') | Some(&op @ b'/') |
+            Some(&op @ b'm') | Some(&op @ b'&') | Some(&op @ b'|') | Some(&op @ b'^') |
+            Some(&op @ b'=') | Some(&op @ b'>') | Some(&op @ b'<') |
+            Some(&op @ b'A') | Some(&op @ b'O') => {
+                let b = try!(state.pop_number());
+                let a = try!(state.pop_number());
+                let r = match op {
+                    b'+' => a + b,
+                    b'-' => a - b,
+                    b'*' => a * b,
+                    b'/' => if b == 0 { 0 } else { a / b },
+                    b'm' => if b == 0 { 0 } else { a % b },
+                    b'&' => a & b,
+                    b'|' => a | b,
+                    b'^' => a ^ b,
+                    b'=' => (a == b) as int,
+                    b'>' => (a > b) as int,
+                    b'<' => (a < b) as int,
+                    b'A' => (a != 0 && b != 0) as int,
+                    b'O' => (a != 0 || b != 0) as int,
+                    _ => unreachable!(),
+                };
+                state.stack.push(Param::Number(r));
+            }
+            Some(&b'!') => {
+                let a = try!(state.pop_number());
+                state.stack.push(Param::Number((a == 0) as int));
+            }
+            Some(&b'~') => {
+                let a = try!(state.pop_number());
+                state.stack.push(Param::Number(!a));
+            }
+            Some(&b'?') => { /* start of an if/then/else; nothing to do */ }
+            Some(&b't') => {
+                let cond = try!(state.pop_number());
+                if cond == 0 {
+                    try!(skip_branch(&mut input));
+                }
+            }
+            Some(&b'e') => {
+                // We only get here after running the `then` branch; skip to %;.
+                try!(skip_to_semi(&mut input));
+            }
+            Some(&b';') => { /* end of an if/then/else; nothing to do */ }
+            Some(&c @ b'd') | Some(&c @ b'o') | Some(&c @ b'x') | Some(&c @ b'X') => {
+                let n = try!(state.pop_number());
+                let s = match c {
+                    b'd' => format!("{}", n),
+                    b'o' => format!("{:o}", n),
+                    b'x' => format!("{:x}", n),
+                    b'X' => format!("{:X}", n),
+                    _ => unreachable!(),
+                };
+                output.push_all(s.as_bytes());
+            }
+            Some(&c) => return Err(format!("unsupported format specifier %{}", c as char)),
+        }
+    }
+
+    Ok(output)
+}
+
+struct State {
+    stack: Vec<Param>,
+    params: Vec<Param>,
+}
+
+impl State {
+    fn pop(&mut self) -> Result<Param, String> {
+        self.stack.pop().ok_or("stack underflow".to_string())
+    }
+
+    fn pop_number(&mut self) -> Result<int, String> {
+        match try!(self.pop()) {
+            Param::Number(n) => Ok(n),
+            Param::String(_) => Err("expected a number, found a string".to_string()),
+        }
+    }
+}
+
+fn skip_branch<'a, I: Iterator<Item=&'a u8>>(input: &mut ::std::iter::Peekable<I>) -> Result<(), String> {
+    // Skip forward to the matching %e or %; at depth 0, honoring nested %?.
+    let mut depth = 0i;
+    loop {
+        match input.next() {
+            None => return Err("unterminated %? conditional".to_string()),
+            Some(&b'%') => match input.next() {
+                Some(&b'?') => depth += 1,
+                Some(&b'e') if depth == 0 => return Ok(()),
+                Some(&b';') if depth == 0 => return Ok(()),
+                Some(&b';') => depth -= 1,
+                _ => {}
+            },
+            Some(_) => {}
+        }
+    }
+}
+
+fn skip_to_semi<'a, I: Iterator<Item=&'a u8>>(input: &mut ::std::iter::Peekable<I>) -> Result<(), String> {
+    let mut depth = 0i;
+    loop {
+        match input.next() {
+            None => return Err("unterminated %? conditional".to_string()),
+            Some(&b'%') => match input.next() {
+                Some(&b'?') => depth += 1,
+                Some(&b';') if depth == 0 => return Ok(()),
+                Some(&b';') => depth -= 1,
+                _ => {}
+            },
+            Some(_) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{expand, Param, Variables};
+
+    #[test]
+    fn expands_a_literal_percent_number_capability() {
+        let out = expand(b"\\E[3%p1%dm", &[Param::Number(2)], &mut Variables::new()).unwrap();
+        assert_eq!(out, b"\\E[32m".to_vec());
+    }
+
+    #[test]
+    fn expands_the_if_then_else_conditional() {
+        // %?%p1%{8}%<%t1%e2%; picks the `then` branch when p1 < 8, else the
+        // `else` branch, as used by `setaf`-style ANSI/non-ANSI color caps.
+        let cap = b"%?%p1%{8}%<%t1%e2%;";
+        let low = expand(cap, &[Param::Number(3)], &mut Variables::new()).unwrap();
+        assert_eq!(low, b"1".to_vec());
+        let high = expand(cap, &[Param::Number(12)], &mut Variables::new()).unwrap();
+        assert_eq!(high, b"2".to_vec());
+    }
+
+    #[test]
+    fn reports_stack_underflow_instead_of_panicking() {
+        assert!(expand(b"%d", &[], &mut Variables::new()).is_err());
+    }
+}
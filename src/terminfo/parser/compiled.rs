@@ -0,0 +1,182 @@
+// Copyright 2013-2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Parses the ncurses-compatible compiled terminfo binary format described
+//! in `term(5)`.
+
+use std::collections::HashMap;
+use std::io::{IoResult, IoError, InvalidInput, Reader};
+
+use super::super::TermInfo;
+use super::names::{BOOLNAMES, NUMNAMES, STRNAMES};
+
+/// The compiled terminfo format's magic number.
+const MAGIC: u16 = 0o0432;
+
+fn malformed(what: &str) -> IoError {
+    IoError {
+        kind: InvalidInput,
+        desc: "malformed terminfo entry",
+        detail: Some(what.to_string()),
+    }
+}
+
+/// Parses a compiled terminfo entry from `file`, following the binary
+/// layout used by `tic`/`infocmp`.
+///
+/// `longnames` is unused for now; it exists so callers that want to
+/// eventually support the extended (long-name) format don't need a second
+/// entry point.
+pub fn parse<R: Reader>(file: &mut R, _longnames: bool) -> IoResult<TermInfo> {
+    if try!(file.read_le_u16()) != MAGIC {
+        return Err(malformed("invalid magic number at start of file"));
+    }
+
+    let names_bytes = try!(file.read_le_u16()) as uint;
+    let bools_count = try!(file.read_le_u16()) as uint;
+    let numbers_count = try!(file.read_le_u16()) as uint;
+    let string_offsets_count = try!(file.read_le_u16()) as uint;
+    let string_table_bytes = try!(file.read_le_u16()) as uint;
+
+    if names_bytes == 0 {
+        return Err(malformed("entry has no names"));
+    }
+
+    let mut raw_names = try!(file.read_exact(names_bytes));
+    if raw_names.last() == Some(&0) {
+        raw_names.pop();
+    }
+    let raw_names = String::from_utf8_lossy(&raw_names[]);
+    let names: Vec<String> = raw_names.split('|').map(|s| s.to_string()).collect();
+
+    let mut bools = HashMap::new();
+    for i in range(0, bools_count) {
+        let on = try!(file.read_byte()) == 1;
+        if on {
+            if let Some(name) = BOOLNAMES.get(i) {
+                bools.insert(name.to_string(), true);
+            }
+        }
+    }
+
+    // A padding byte is inserted if the names + bools section has an odd
+    // length, to keep the subsequent i16 values aligned.
+    if (names_bytes + bools_count) % 2 == 1 {
+        try!(file.read_byte());
+    }
+
+    let mut numbers = HashMap::new();
+    for i in range(0, numbers_count) {
+        let n = try!(file.read_le_u16());
+        if n != 0xffff {
+            if let Some(name) = NUMNAMES.get(i) {
+                numbers.insert(name.to_string(), n);
+            }
+        }
+    }
+
+    let mut string_offsets = Vec::with_capacity(string_offsets_count);
+    for _ in range(0, string_offsets_count) {
+        string_offsets.push(try!(file.read_le_u16()));
+    }
+
+    let string_table = try!(file.read_exact(string_table_bytes));
+
+    let mut strings = HashMap::new();
+    for (i, &offset) in string_offsets.iter().enumerate() {
+        if offset == 0xffff {
+            continue;
+        }
+        let offset = offset as uint;
+        if offset > string_table.len() {
+            return Err(malformed("string offset out of range"));
+        }
+        let end = string_table[offset..].iter().position(|&b| b == 0)
+            .map_or(string_table.len(), |p| offset + p);
+        if let Some(name) = STRNAMES.get(i) {
+            strings.insert(name.to_string(), string_table[offset..end].to_vec());
+        }
+    }
+
+    Ok(TermInfo {
+        names: names,
+        bools: bools,
+        numbers: numbers,
+        strings: strings,
+    })
+}
+
+/// A minimal built-in entry for `mintty`-based MSYS consoles, which report
+/// `TERM=xterm` (or similar) but don't ship a terminfo database at all in a
+/// plain MSYS install.
+pub fn msys_terminfo() -> TermInfo {
+    let mut strings = HashMap::new();
+    strings.insert("sgr0".to_string(), b"\x1b[0m".to_vec());
+    strings.insert("bold".to_string(), b"\x1b[1m".to_vec());
+    strings.insert("setaf".to_string(), b"\x1b[3%p1%dm".to_vec());
+    strings.insert("setab".to_string(), b"\x1b[4%p1%dm".to_vec());
+
+    let mut numbers = HashMap::new();
+    numbers.insert("colors".to_string(), 8u16);
+
+    TermInfo {
+        names: vec!["cygwin".to_string()],
+        bools: HashMap::new(),
+        numbers: numbers,
+        strings: strings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::MemReader;
+    use super::{parse, MAGIC};
+
+    fn write_le_u16(buf: &mut Vec<u8>, v: u16) {
+        buf.push(v as u8);
+        buf.push((v >> 8) as u8);
+    }
+
+    /// Builds the bytes of a minimal compiled terminfo entry with a single
+    /// name, no bools or numbers, and one string capability at slot 0
+    /// (`cbt`), so the round trip and offset-bounds tests don't have to
+    /// duplicate the whole binary layout.
+    fn entry_with_one_string(string_table: &[u8], offset: u16) -> Vec<u8> {
+        let names = b"xterm";
+        let mut buf = Vec::new();
+        write_le_u16(&mut buf, MAGIC);
+        write_le_u16(&mut buf, names.len() as u16);
+        write_le_u16(&mut buf, 0); // bools_count
+        write_le_u16(&mut buf, 0); // numbers_count
+        write_le_u16(&mut buf, 1); // string_offsets_count
+        write_le_u16(&mut buf, string_table.len() as u16);
+        buf.push_all(names);
+        if names.len() % 2 == 1 {
+            buf.push(0); // alignment padding
+        }
+        write_le_u16(&mut buf, offset);
+        buf.push_all(string_table);
+        buf
+    }
+
+    #[test]
+    fn parses_a_minimal_entry() {
+        let bytes = entry_with_one_string(b"\x1b[Z\0", 0);
+        let info = parse(&mut MemReader::new(bytes), false).unwrap();
+        assert_eq!(info.names, vec!["xterm".to_string()]);
+        assert_eq!(info.strings.get("cbt"), Some(&b"\x1b[Z".to_vec()));
+    }
+
+    #[test]
+    fn rejects_an_out_of_range_string_offset() {
+        let bytes = entry_with_one_string(b"\0", 5);
+        assert!(parse(&mut MemReader::new(bytes), false).is_err());
+    }
+}
@@ -0,0 +1,101 @@
+// Copyright 2013-2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Locates terminfo database entries on disk.
+
+use std::io::fs::File;
+use std::io::{IoResult, IoError, FileNotFound};
+use std::os;
+
+/// The standard system locations searched for a terminfo database, in
+/// order, once the user's own overrides have been exhausted.
+fn default_dirs() -> Vec<String> {
+    vec![
+        "/usr/share/terminfo".to_string(),
+        "/etc/terminfo".to_string(),
+        "/usr/lib/terminfo".to_string(),
+        "/usr/local/share/terminfo".to_string(),
+    ]
+}
+
+/// Builds the ordered list of directories to search, honoring the same
+/// environment variables ncurses-compatible tools do: `$TERMINFO` (a single
+/// directory, checked first), then `$HOME/.terminfo`, then each entry of
+/// `$TERMINFO_DIRS` (colon-separated; an empty entry means "insert the
+/// compiled-in default locations here"), and finally the standard system
+/// locations, unless an empty `$TERMINFO_DIRS` entry already placed them.
+fn search_dirs() -> Vec<String> {
+    let mut dirs = Vec::new();
+
+    if let Some(dir) = os::getenv("TERMINFO") {
+        dirs.push(dir);
+    }
+
+    if let Some(home) = os::getenv("HOME") {
+        dirs.push(Path::new(home).join(".terminfo").display().to_string());
+    }
+
+    let mut defaults_inserted = false;
+    if let Some(dirs_env) = os::getenv("TERMINFO_DIRS") {
+        for dir in dirs_env.as_slice().split(':') {
+            if dir.is_empty() {
+                dirs.extend(default_dirs().into_iter());
+                defaults_inserted = true;
+            } else {
+                dirs.push(dir.to_string());
+            }
+        }
+    }
+
+    if !defaults_inserted {
+        dirs.extend(default_dirs().into_iter());
+    }
+    dirs
+}
+
+/// Opens the terminfo database entry for `term`, trying each candidate
+/// directory in turn.
+pub fn open(term: &str) -> IoResult<File> {
+    if term.len() == 0 {
+        return Err(IoError {
+            kind: FileNotFound,
+            desc: "empty terminal name",
+            detail: None,
+        });
+    }
+
+    for dir in search_dirs().into_iter() {
+        if let Some(file) = try_dir(&dir, term) {
+            return Ok(file);
+        }
+    }
+
+    Err(IoError {
+        kind: FileNotFound,
+        desc: "could not find terminfo entry",
+        detail: Some(format!("no terminfo entry found for {}", term)),
+    })
+}
+
+/// Tries both subdirectory layouts ncurses uses underneath a single
+/// terminfo root: `<first-letter>/<name>` and, for terminals whose first
+/// character isn't easily used as a directory name, `<hex-byte>/<name>`.
+fn try_dir(dir: &str, term: &str) -> Option<File> {
+    let first = term.char_at(0);
+
+    let by_letter = Path::new(dir).join(first.to_string()).join(term);
+    if let Ok(file) = File::open(&by_letter) {
+        return Some(file);
+    }
+
+    let hex = format!("{:02x}", first as uint);
+    let by_hex = Path::new(dir).join(hex).join(term);
+    File::open(&by_hex).ok()
+}
@@ -0,0 +1,193 @@
+// Copyright 2012-2014 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Windows console backend.
+//!
+//! A stock `cmd.exe` console has no terminfo database and does not
+//! understand ANSI escapes, so `TerminfoTerminal` is useless there. This
+//! module talks directly to the Win32 Console API instead.
+
+use std::io::IoResult;
+use std::mem;
+
+use Attr;
+use color;
+use Terminal;
+
+#[allow(non_camel_case_types)]
+type WORD = u16;
+#[allow(non_camel_case_types)]
+type DWORD = u32;
+#[allow(non_camel_case_types)]
+type SHORT = i16;
+#[allow(non_camel_case_types)]
+type BOOL = i32;
+#[allow(non_camel_case_types)]
+type HANDLE = *mut u8;
+
+pub const STD_OUTPUT_HANDLE: DWORD = -11i32 as DWORD;
+pub const STD_ERROR_HANDLE: DWORD = -12i32 as DWORD;
+
+const FOREGROUND_BLUE: WORD = 0x1;
+const FOREGROUND_GREEN: WORD = 0x2;
+const FOREGROUND_RED: WORD = 0x4;
+const FOREGROUND_INTENSITY: WORD = 0x8;
+const BACKGROUND_BLUE: WORD = 0x10;
+const BACKGROUND_GREEN: WORD = 0x20;
+const BACKGROUND_RED: WORD = 0x40;
+const BACKGROUND_INTENSITY: WORD = 0x80;
+
+#[allow(non_snake_case)]
+#[repr(C)]
+struct COORD {
+    X: SHORT,
+    Y: SHORT,
+}
+
+#[allow(non_snake_case)]
+#[repr(C)]
+struct SMALL_RECT {
+    Left: SHORT,
+    Top: SHORT,
+    Right: SHORT,
+    Bottom: SHORT,
+}
+
+#[allow(non_snake_case)]
+#[repr(C)]
+struct CONSOLE_SCREEN_BUFFER_INFO {
+    dwSize: COORD,
+    dwCursorPosition: COORD,
+    wAttributes: WORD,
+    srWindow: SMALL_RECT,
+    dwMaximumWindowSize: COORD,
+}
+
+#[allow(non_snake_case)]
+extern "system" {
+    fn GetStdHandle(nStdHandle: DWORD) -> HANDLE;
+    fn GetConsoleScreenBufferInfo(hConsoleOutput: HANDLE,
+                                  lpConsoleScreenBufferInfo: *mut CONSOLE_SCREEN_BUFFER_INFO)
+                                  -> BOOL;
+    fn SetConsoleTextAttribute(hConsoleOutput: HANDLE, wAttributes: WORD) -> BOOL;
+}
+
+/// Maps one of our `color::Color` indices onto the low nibble of a Windows
+/// console attribute word.
+///
+/// The ANSI bit order is RED=1, GREEN=2, BLUE=4; Windows swaps bits 0 and 2
+/// relative to that, so RED=4, GREEN=2, BLUE=1. Bits 1 (green) and 3
+/// (intensity) are in the same position in both encodings.
+fn color_to_bits(color: color::Color) -> WORD {
+    let bits = color as WORD;
+    ((bits & 1) << 2) | (bits & 2) | ((bits & 4) >> 2) | (bits & 8)
+}
+
+/// A `Terminal` implementation that drives the Win32 Console API directly,
+/// for use on consoles with no terminfo database (e.g. a stock `cmd.exe`).
+pub struct WinConsole<T> {
+    buf: T,
+    handle: DWORD,
+    def_attributes: WORD,
+    attributes: WORD,
+}
+
+fn conout_attributes(handle: DWORD) -> WORD {
+    unsafe {
+        let mut info: CONSOLE_SCREEN_BUFFER_INFO = mem::zeroed();
+        let handle = GetStdHandle(handle);
+        if GetConsoleScreenBufferInfo(handle, &mut info) != 0 {
+            info.wAttributes
+        } else {
+            FOREGROUND_RED | FOREGROUND_GREEN | FOREGROUND_BLUE
+        }
+    }
+}
+
+impl<T: Writer+Send> WinConsole<T> {
+    /// Creates a new `WinConsole` over the console buffer identified by
+    /// `handle` (`STD_OUTPUT_HANDLE` or `STD_ERROR_HANDLE`), capturing its
+    /// current attribute word so that `reset()` can restore it later.
+    pub fn new(out: T, handle: DWORD) -> WinConsole<T> {
+        let attributes = conout_attributes(handle);
+        WinConsole {
+            buf: out,
+            handle: handle,
+            def_attributes: attributes,
+            attributes: attributes,
+        }
+    }
+
+    fn set_attributes(&mut self, attributes: WORD) -> IoResult<bool> {
+        self.attributes = attributes;
+        let ok = unsafe {
+            SetConsoleTextAttribute(GetStdHandle(self.handle), self.attributes)
+        };
+        Ok(ok != 0)
+    }
+}
+
+impl<T: Writer+Send> Terminal<T> for WinConsole<T> {
+    fn fg(&mut self, color: color::Color) -> IoResult<bool> {
+        let bits = color_to_bits(color);
+        let attributes = (self.attributes & !0xf) | bits;
+        self.set_attributes(attributes)
+    }
+
+    fn bg(&mut self, color: color::Color) -> IoResult<bool> {
+        let bits = color_to_bits(color) << 4;
+        let attributes = (self.attributes & !0xf0) | bits;
+        self.set_attributes(attributes)
+    }
+
+    fn attr(&mut self, attr: Attr) -> IoResult<bool> {
+        match attr {
+            Attr::ForegroundColor(c) => self.fg(c),
+            Attr::BackgroundColor(c) => self.bg(c),
+            Attr::Bold => {
+                let attributes = self.attributes | FOREGROUND_INTENSITY;
+                self.set_attributes(attributes)
+            }
+            Attr::Reverse => {
+                let attributes = ((self.attributes & 0xf) << 4) | ((self.attributes & 0xf0) >> 4)
+                    | (self.attributes & !0xff);
+                self.set_attributes(attributes)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    fn supports_attr(&self, attr: Attr) -> bool {
+        match attr {
+            Attr::ForegroundColor(_) | Attr::BackgroundColor(_) |
+            Attr::Bold | Attr::Reverse => true,
+            _ => false,
+        }
+    }
+
+    fn reset(&mut self) -> IoResult<bool> {
+        let def_attributes = self.def_attributes;
+        self.set_attributes(def_attributes)
+    }
+
+    fn get_ref<'a>(&'a self) -> &'a T { &self.buf }
+
+    fn get_mut<'a>(&'a mut self) -> &'a mut T { &mut self.buf }
+}
+
+impl<T: Writer> Writer for WinConsole<T> {
+    fn write(&mut self, buf: &[u8]) -> IoResult<()> {
+        self.buf.write(buf)
+    }
+
+    fn flush(&mut self) -> IoResult<()> {
+        self.buf.flush()
+    }
+}